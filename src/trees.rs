@@ -6,4 +6,4 @@ mod named_lu_tree;
 pub use linked_tree::Node;
 pub use linked_tree::add_child_to_parent;
 pub use lu_tree::LuTree;
-pub use named_lu_tree::NamedLuTree;
\ No newline at end of file
+pub use named_lu_tree::{Bfs, Dfs, NamedLuTree};
\ No newline at end of file
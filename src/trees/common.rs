@@ -1,4 +1,5 @@
-use std::collections::VecDeque;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
 
 /// Trait containing node buffers used during search
 /// 
@@ -36,4 +37,60 @@ impl<T> SearchBuffer<T> for VecDeque<T> {
 #[derive(Debug,Clone,PartialEq)]
 pub struct NodeData {
     pub value: f32
+}
+
+/// An item enqueued in a `PriorityBuffer`, ordered so the lowest `priority`
+/// is popped first (`BinaryHeap` is a max-heap, so the ordering is reversed)
+struct PriorityItem<T> {
+    priority: f32,
+    value: T,
+}
+
+impl<T> PartialEq for PriorityItem<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<T> Eq for PriorityItem<T> {}
+
+impl<T> PartialOrd for PriorityItem<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for PriorityItem<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.partial_cmp(&self.priority).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// A `SearchBuffer` that enlists values in a `BinaryHeap` ordered by an `f32`
+/// key, so `get_next` always returns the lowest-cost entry first
+///
+/// This lets `NamedLuTree::traverse` do best-first / greedy search with the
+/// same machinery that already powers its stack- and queue-based DFS/BFS.
+pub struct PriorityBuffer<T, F: Fn(&T) -> f32> {
+    heap: BinaryHeap<PriorityItem<T>>,
+    key: F,
+}
+
+impl<T, F: Fn(&T) -> f32> PriorityBuffer<T, F> {
+    pub fn new(key: F) -> PriorityBuffer<T, F> {
+        PriorityBuffer { heap: BinaryHeap::new(), key }
+    }
+}
+
+impl<T, F: Fn(&T) -> f32> SearchBuffer<T> for PriorityBuffer<T, F> {
+    fn enlist(&mut self, val: T) {
+        let priority = (self.key)(&val);
+        self.heap.push(PriorityItem { priority, value: val });
+    }
+    fn get_next(&mut self) -> Option<T> {
+        self.heap.pop().map(|item| item.value)
+    }
+    fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
 }
\ No newline at end of file
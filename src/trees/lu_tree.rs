@@ -8,10 +8,14 @@ macro_rules! get_from_tree {
     };
 }
 
+use std::collections::HashMap;
+
 pub struct LuTree<T> {
     parents: Vec<Option<usize>>,
     children: Vec<Vec<usize>>,
-    data: Vec<T>,
+    data: Vec<Option<T>>,
+    occupied: Vec<bool>,
+    free: Vec<usize>,
 }
 
 impl<T> LuTree<T> where
@@ -21,62 +25,188 @@ impl<T> LuTree<T> where
             parents: vec![],
             children: vec![],
             data: vec![],
+            occupied: vec![],
+            free: vec![],
         }
 
     }
 
     pub fn add_node(&mut self, parent: Option<usize>, data: T) -> Result<usize, ()> {
-        match parent {
-            Some(p) => {
-                if p > self.parents.len() {
-                    return Err(());
-                }
+        if let Some(p) = parent {
+            if p >= self.parents.len() || !self.occupied[p] {
+                return Err(());
+            }
+        }
 
-                self.parents.push(Some(p));
+        let node_id = match self.free.pop() {
+            Some(idx) => {
+                self.parents[idx] = parent;
+                self.children[idx] = vec![];
+                self.data[idx] = Some(data);
+                self.occupied[idx] = true;
+                idx
             },
-            None => self.parents.push(None),
-        }
+            None => {
+                self.parents.push(parent);
+                self.children.push(vec![]);
+                self.data.push(Some(data));
+                self.occupied.push(true);
+                self.parents.len() - 1
+            },
+        };
 
-        let node_id = self.parents.len();
-        self.children.push(vec![]);
-        self.data.push(data);
+        if let Some(p) = parent {
+            self.children[p].push(node_id);
+        }
 
         Ok(node_id)
     }
 
     pub fn set(&mut self, node: usize, data: T) -> Result<(), &str> {
-        match node {
-            n if node < self.data.len() => {
-                self.data[n] = data;
+        match self.occupied.get(node) {
+            Some(true) => {
+                self.data[node] = Some(data);
                 Ok(())
             },
-            _ => Err("Access out of bounds!"),
+            Some(false) => Err("Node has been removed!"),
+            None => Err("Access out of bounds!"),
         }
     }
 
     pub fn get(&self, node: usize) -> Result<T, &str> {
-        get_from_tree!(self.data, node)
+        match self.occupied.get(node) {
+            Some(true) => Ok(self.data[node].clone().unwrap()),
+            Some(false) => Err("Node has been removed!"),
+            None => Err("Access out of bounds!"),
+        }
     }
 
     pub fn parent(&self, node: usize) -> Result<usize, &str> {
-        let p_res = get_from_tree!(self.parents, node);
-        match p_res {
-            Ok(p_opt) => match p_opt {
-                Some(p) => Ok(p),
-                None => Err("Node has not parent"),
-            },
-            Err(e) => Err(e),
+        match self.occupied.get(node) {
+            Some(true) => self.parents[node].ok_or("Node has not parent"),
+            Some(false) => Err("Node has been removed!"),
+            None => Err("Access out of bounds!"),
         }
     }
 
     pub fn children(&self, node: usize) -> Result<Vec<usize>, &str> {
-        get_from_tree!(self.children, node)
+        match self.occupied.get(node) {
+            Some(true) => Ok(self.children[node].clone()),
+            Some(false) => Err("Node has been removed!"),
+            None => Err("Access out of bounds!"),
+        }
+    }
+
+    pub fn fold<R, F: FnMut(&T, &[R]) -> R>(&self, root: usize, mut f: F) -> Result<R, &str> {
+        if self.occupied.get(root) != Some(&true) {
+            return Err("Access out of bounds!");
+        }
+
+        enum Step {
+            Enter(usize),
+            Exit(usize),
+        }
+
+        let mut stack = vec![Step::Enter(root)];
+        let mut folded: HashMap<usize, R> = HashMap::new();
+
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Enter(node) => {
+                    stack.push(Step::Exit(node));
+                    for &child in &self.children[node] {
+                        stack.push(Step::Enter(child));
+                    }
+                },
+                Step::Exit(node) => {
+                    let child_results: Vec<R> = self.children[node]
+                        .iter()
+                        .map(|child| folded.remove(child).expect("child folded before parent"))
+                        .collect();
+                    let data = self.data[node].as_ref().expect("occupied node has data");
+                    folded.insert(node, f(data, &child_results));
+                },
+            }
+        }
+
+        Ok(folded.remove(&root).expect("root folded"))
+    }
+
+    /// Detach `node` from its parent and tombstone it and all its
+    /// descendants, returning their data in (pre-order) traversal order
+    ///
+    /// Vacated slots are tracked in `free` and recycled by `add_node`, so
+    /// indices of nodes that are not removed stay stable.
+    pub fn remove_subtree(&mut self, node: usize) -> Result<Vec<T>, &str> {
+        match self.occupied.get(node) {
+            Some(true) => {},
+            Some(false) => return Err("Node has been removed!"),
+            None => return Err("Access out of bounds!"),
+        }
+
+        if let Some(p) = self.parents[node] {
+            self.children[p].retain(|&c| c != node);
+        }
+
+        let mut removed = Vec::new();
+        let mut stack = vec![node];
+
+        while let Some(n) = stack.pop() {
+            removed.push(self.data[n].take().unwrap());
+            stack.extend(self.children[n].iter().rev().copied());
+
+            self.occupied[n] = false;
+            self.parents[n] = None;
+            self.children[n] = Vec::new();
+            self.free.push(n);
+        }
+
+        Ok(removed)
     }
+
+    pub fn walk<V: Visitor<T>>(&self, root: usize, visitor: &mut V) -> Result<(), &str> {
+        if self.occupied.get(root) != Some(&true) {
+            return Err("Access out of bounds!");
+        }
+
+        let mut stack = vec![Visit::Leave(root), Visit::Enter(root)];
+
+        while let Some(event) = stack.pop() {
+            match event {
+                Visit::Enter(node) => {
+                    visitor.enter(node, self.data[node].as_ref().expect("occupied node has data"));
+                    for &child in self.children[node].iter().rev() {
+                        stack.push(Visit::Leave(child));
+                        stack.push(Visit::Enter(child));
+                    }
+                },
+                Visit::Leave(node) => {
+                    visitor.leave(node, self.data[node].as_ref().expect("occupied node has data"));
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Event emitted while walking a `LuTree`, see `LuTree::walk`
+pub enum Visit {
+    Enter(usize),
+    Leave(usize),
+}
+
+/// Callbacks driven by `LuTree::walk`, one pre-order and one post-order hook
+/// per node, with default no-op bodies so callers only implement what they need
+pub trait Visitor<T> {
+    fn enter(&mut self, _node: usize, _data: &T) {}
+
+    fn leave(&mut self, _node: usize, _data: &T) {}
 }
 
 #[cfg(test)]
 mod test {
-    use super::{LuTree};
+    use super::{LuTree, Visitor};
 
     #[test]
     fn create_lu_trees() {
@@ -111,4 +241,107 @@ mod test {
         assert_eq!(tree.get(3).unwrap(), new_data);
 
     }
+
+    #[test]
+    fn fold_subtree_sizes() {
+        // File(size) leaves fold to their own size, Dir(0) folds to the sum
+        // of its children's folded sizes.
+        let mut tree = LuTree::new();
+        let root = tree.add_node(None, 0).unwrap();
+        let dir_a = tree.add_node(Some(root), 0).unwrap();
+        let _ = tree.add_node(Some(dir_a), 3);
+        let _ = tree.add_node(Some(dir_a), 5);
+        let _ = tree.add_node(Some(root), 7);
+
+        let total = tree.fold(root, |data, child_sizes| {
+            if child_sizes.is_empty() {
+                *data
+            } else {
+                child_sizes.iter().sum()
+            }
+        }).unwrap();
+
+        assert_eq!(total, 15);
+    }
+
+    #[test]
+    fn fold_out_of_bounds() {
+        let tree: LuTree<i32> = LuTree::new();
+        assert_eq!(tree.fold(0, |data, _: &[i32]| *data), Err("Access out of bounds!"));
+    }
+
+    #[test]
+    fn add_node_returns_real_index() {
+        let mut tree = LuTree::new();
+        let root = tree.add_node(None, "root").unwrap();
+        assert_eq!(root, 0);
+        assert_eq!(tree.get(root).unwrap(), "root");
+    }
+
+    #[test]
+    fn remove_subtree_detaches_and_tombstones() {
+        let mut tree = LuTree::new();
+        let root = tree.add_node(None, "root").unwrap();
+        let dir_a = tree.add_node(Some(root), "dir_a").unwrap();
+        let file_a1 = tree.add_node(Some(dir_a), "file_a1").unwrap();
+        let file_root = tree.add_node(Some(root), "file_root").unwrap();
+
+        let removed = tree.remove_subtree(dir_a).unwrap();
+        assert_eq!(removed, vec!["dir_a", "file_a1"]);
+
+        // Removed nodes are gone, the parent no longer lists them as a child
+        assert_eq!(tree.get(dir_a), Err("Node has been removed!"));
+        assert_eq!(tree.get(file_a1), Err("Node has been removed!"));
+        assert_eq!(tree.children(root).unwrap(), vec![file_root]);
+
+        // Vacated slots get recycled on the next insertions
+        let reused_1 = tree.add_node(Some(root), "new_a").unwrap();
+        let reused_2 = tree.add_node(Some(root), "new_b").unwrap();
+        assert_eq!(vec![reused_1, reused_2].iter().collect::<std::collections::HashSet<_>>().len(), 2);
+        assert!(reused_1 == dir_a || reused_1 == file_a1);
+        assert!(reused_2 == dir_a || reused_2 == file_a1);
+    }
+
+    #[test]
+    fn remove_subtree_out_of_bounds() {
+        let mut tree: LuTree<i32> = LuTree::new();
+        assert_eq!(tree.remove_subtree(0), Err("Access out of bounds!"));
+    }
+
+    #[test]
+    fn walk_visits_enter_and_leave_in_order() {
+        struct Log(Vec<String>);
+
+        impl Visitor<&'static str> for Log {
+            fn enter(&mut self, _node: usize, data: &&'static str) {
+                self.0.push(format!("enter {}", data));
+            }
+
+            fn leave(&mut self, _node: usize, data: &&'static str) {
+                self.0.push(format!("leave {}", data));
+            }
+        }
+
+        let mut tree = LuTree::new();
+        let root = tree.add_node(None, "root").unwrap();
+        let child_a = tree.add_node(Some(root), "a").unwrap();
+        let _ = tree.add_node(Some(root), "b").unwrap();
+        let _ = tree.add_node(Some(child_a), "a1").unwrap();
+
+        let mut log = Log(Vec::new());
+        tree.walk(root, &mut log).unwrap();
+
+        assert_eq!(log.0, vec![
+            "enter root", "enter a", "enter a1", "leave a1", "leave a", "enter b", "leave b", "leave root",
+        ]);
+    }
+
+    #[test]
+    fn walk_out_of_bounds() {
+        struct NoOp;
+        impl Visitor<i32> for NoOp {}
+
+        let tree: LuTree<i32> = LuTree::new();
+        assert_eq!(tree.walk(0, &mut NoOp), Err("Access out of bounds!"));
+    }
 }
\ No newline at end of file
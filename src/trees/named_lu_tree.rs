@@ -1,9 +1,9 @@
 // Copyright 2021 Simon B. Gasse
 
 use std::fs;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 
-use crate::trees::common::SearchBuffer;
+use crate::trees::common::{PriorityBuffer, SearchBuffer};
 
 #[derive(Debug)]
 /// Structure representing a look-up tree with String as node names
@@ -45,14 +45,41 @@ impl NamedLuTree {
 
     /// Runs a depth-first-search on the look-up tree
     pub fn dfs(&self, start: String) -> Result<Vec<String>, &str> {
-        let mut stack: Vec<usize> = Vec::new();
-        self.traverse(start, &mut stack)
+        Ok(self.dfs_iter(&start)?.map(str::to_string).collect())
     }
 
     /// Runs a breadth-first-search on the look-up tree
     pub fn bfs(&self, start: String) -> Result<Vec<String>, &str> {
-        let mut queue: VecDeque<usize> = VecDeque::new();
-        self.traverse(start, &mut queue)
+        Ok(self.bfs_iter(&start)?.map(str::to_string).collect())
+    }
+
+    /// Runs a best-first (greedy) search, visiting the lowest-cost node
+    /// known so far on each step
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - A String defining the name of the start node for traversal
+    /// * `key` - Cost function mapping a node index to its priority; lower visits first
+    pub fn best_first(&self, start: String, key: impl Fn(usize) -> f32) -> Result<Vec<String>, &str> {
+        let mut buffer = PriorityBuffer::new(|&node: &usize| key(node));
+        self.traverse(start, &mut buffer)
+    }
+
+    /// Returns a lazy depth-first iterator over node names, starting at `start`
+    pub fn dfs_iter(&self, start: &str) -> Result<Dfs<'_>, &str> {
+        self.traversal_from(start, Vec::new())
+    }
+
+    /// Returns a lazy breadth-first iterator over node names, starting at `start`
+    pub fn bfs_iter(&self, start: &str) -> Result<Bfs<'_>, &str> {
+        self.traversal_from(start, VecDeque::new())
+    }
+
+    /// Build a `Traversal` seeded with `start`, using `buffer` to order visits
+    fn traversal_from<B: SearchBuffer<usize>>(&self, start: &str, mut buffer: B) -> Result<Traversal<'_, B>, &str> {
+        let &start_idx = self.name2idx.get(start).ok_or("Start node not found.")?;
+        buffer.enlist(start_idx);
+        Ok(Traversal { tree: self, buffer, visited: HashSet::new() })
     }
 
     /// Parse a line for creating a look-up tree from file
@@ -140,8 +167,139 @@ impl NamedLuTree {
 
     }
 
+    /// Resolve many root-to-node name-paths in one batched walk
+    ///
+    /// Groups the paths by the name they share at each depth so that shared
+    /// prefixes (e.g. `["a","b","c"]` and `["a","b","d"]`) are only resolved
+    /// once, instead of walking each path independently from the root.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - Root-to-node paths, each expressed as a sequence of node names
+    pub fn resolve_paths(&self, paths: &[Vec<String>]) -> HashMap<Vec<String>, Option<usize>> {
+        let mut results = HashMap::new();
+        let entries: Vec<(usize, &[String])> = paths
+            .iter()
+            .enumerate()
+            .map(|(i, path)| (i, path.as_slice()))
+            .collect();
+
+        self.resolve_group(None, entries, paths, &mut results);
+
+        results
+    }
+
+    /// Resolve one depth level for a group of paths that share `current` as
+    /// their common ancestor, then recurse into the next depth level
+    fn resolve_group(
+        &self,
+        current: Option<usize>,
+        entries: Vec<(usize, &[String])>,
+        paths: &[Vec<String>],
+        results: &mut HashMap<Vec<String>, Option<usize>>,
+    ) {
+        let mut by_component: BTreeMap<String, Vec<(usize, &[String])>> = BTreeMap::new();
+
+        for (idx, remaining) in entries {
+            match remaining.split_first() {
+                None => {
+                    results.insert(paths[idx].clone(), current);
+                }
+                Some((head, tail)) => {
+                    by_component.entry(head.clone()).or_insert_with(Vec::new).push((idx, tail));
+                }
+            }
+        }
+
+        for (component, group) in by_component {
+            match self.child_by_name(current, &component) {
+                Some(next) => self.resolve_group(Some(next), group, paths, results),
+                None => {
+                    for (idx, _) in group {
+                        results.insert(paths[idx].clone(), None);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Find the child of `current` (or a top-level node when `current` is
+    /// `None`) whose name matches `name`
+    fn child_by_name(&self, current: Option<usize>, name: &str) -> Option<usize> {
+        match current {
+            Some(parent) => self.children[parent]
+                .iter()
+                .copied()
+                .find(|&c| *self.names[c] == name),
+            None => self
+                .name2idx
+                .get(name)
+                .copied()
+                .filter(|&idx| self.parents[idx].is_none()),
+        }
+    }
+
+    /// Find the node indices for every non-empty prefix of `path` (including
+    /// `path` itself) that exists in the tree, stopping at the first
+    /// component with no match
+    pub fn find_prefixes(&self, path: &[String]) -> Vec<usize> {
+        let mut prefixes = Vec::new();
+        let mut current = None;
+
+        for name in path {
+            match self.child_by_name(current, name) {
+                Some(idx) => {
+                    prefixes.push(idx);
+                    current = Some(idx);
+                },
+                None => break,
+            }
+        }
+
+        prefixes
+    }
+
+    /// Find the deepest existing node along `path`, i.e. the node for its
+    /// longest prefix that exists in the tree
+    pub fn find_longest_prefix(&self, path: &[String]) -> Option<usize> {
+        self.find_prefixes(path).last().copied()
+    }
+
 }
 
+/// Lazy traversal over a `NamedLuTree`, ordered by its `SearchBuffer`
+///
+/// Advances one node per `next()` call instead of materializing the whole
+/// visit order up front, so callers can short-circuit with `find`, `take`,
+/// `any`, etc.
+pub struct Traversal<'a, B> {
+    tree: &'a NamedLuTree,
+    buffer: B,
+    visited: HashSet<usize>,
+}
+
+/// Depth-first lazy traversal, see [`Traversal`]
+pub type Dfs<'a> = Traversal<'a, Vec<usize>>;
+
+/// Breadth-first lazy traversal, see [`Traversal`]
+pub type Bfs<'a> = Traversal<'a, VecDeque<usize>>;
+
+impl<'a, B: SearchBuffer<usize>> Iterator for Traversal<'a, B> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node_idx = self.buffer.get_next()?;
+
+        for child in &self.tree.children[node_idx] {
+            if !self.visited.contains(child) {
+                self.buffer.enlist(*child);
+            }
+        }
+
+        self.visited.insert(node_idx);
+        Some(self.tree.names[node_idx].as_str())
+    }
+}
 
 #[cfg(test)]
 mod test {
@@ -218,4 +376,104 @@ mod test {
         assert_eq!(parent1, "A".to_string());
         assert_eq!(children1, ref_children);
     }
+
+    #[test]
+    fn test_resolve_paths() {
+        let mut gr = NamedLuTree::new();
+        gr.add_with_children_r("A", vec!["B"]);
+        gr.add_with_children_r("B", vec!["C", "D"]);
+
+        let path_bc: Vec<String> = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        let path_bd: Vec<String> = vec!["A".to_string(), "B".to_string(), "D".to_string()];
+        let path_missing: Vec<String> = vec!["A".to_string(), "B".to_string(), "X".to_string()];
+
+        let paths = vec![path_bc.clone(), path_bd.clone(), path_missing.clone()];
+        let resolved = gr.resolve_paths(&paths);
+
+        let c_idx = *gr.name2idx.get("C").unwrap();
+        let d_idx = *gr.name2idx.get("D").unwrap();
+
+        assert_eq!(resolved.get(&path_bc), Some(&Some(c_idx)));
+        assert_eq!(resolved.get(&path_bd), Some(&Some(d_idx)));
+        assert_eq!(resolved.get(&path_missing), Some(&None));
+    }
+
+    #[test]
+    fn test_dfs_iter_short_circuits() {
+        let mut gr = NamedLuTree::new();
+        gr.add_with_children_r("A", vec!["B", "C"]);
+
+        let mut it = gr.dfs_iter("A").unwrap();
+        assert_eq!(it.next(), Some("A"));
+        assert!(it.next().is_some());
+
+        // Collecting the rest still yields every remaining node exactly once.
+        let rest: Vec<&str> = it.collect();
+        assert_eq!(rest.len(), 1);
+    }
+
+    #[test]
+    fn test_bfs_iter_matches_eager_bfs() {
+        let mut gr = NamedLuTree::new();
+        gr.add_with_children_r("A", vec!["B", "C"]);
+
+        let eager = gr.bfs("A".to_string()).unwrap();
+        let lazy: Vec<String> = gr.bfs_iter("A").unwrap().map(str::to_string).collect();
+        assert_eq!(eager, lazy);
+    }
+
+    #[test]
+    fn test_find_prefixes() {
+        let mut gr = NamedLuTree::new();
+        gr.add_with_children_r("A", vec!["B"]);
+        gr.add_with_children_r("B", vec!["C"]);
+
+        let a_idx = *gr.name2idx.get("A").unwrap();
+        let b_idx = *gr.name2idx.get("B").unwrap();
+        let c_idx = *gr.name2idx.get("C").unwrap();
+
+        let path = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        assert_eq!(gr.find_prefixes(&path), vec![a_idx, b_idx, c_idx]);
+
+        let path_with_gap = vec!["A".to_string(), "X".to_string(), "C".to_string()];
+        assert_eq!(gr.find_prefixes(&path_with_gap), vec![a_idx]);
+
+        assert_eq!(gr.find_prefixes(&[]), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_find_longest_prefix() {
+        let mut gr = NamedLuTree::new();
+        gr.add_with_children_r("A", vec!["B"]);
+        gr.add_with_children_r("B", vec!["C"]);
+
+        let c_idx = *gr.name2idx.get("C").unwrap();
+
+        let path = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        assert_eq!(gr.find_longest_prefix(&path), Some(c_idx));
+
+        let path_with_gap = vec!["A".to_string(), "B".to_string(), "X".to_string(), "C".to_string()];
+        let b_idx = *gr.name2idx.get("B").unwrap();
+        assert_eq!(gr.find_longest_prefix(&path_with_gap), Some(b_idx));
+
+        assert_eq!(gr.find_longest_prefix(&["X".to_string()]), None);
+    }
+
+    #[test]
+    fn test_best_first() {
+        use std::collections::HashMap;
+
+        let mut gr = NamedLuTree::new();
+        gr.add_with_children_r("A", vec!["B", "C"]);
+
+        // Visit "C" before "B" despite "B" being enlisted first.
+        let costs: HashMap<usize, f32> = [
+            (*gr.name2idx.get("A").unwrap(), 0.0),
+            (*gr.name2idx.get("B").unwrap(), 2.0),
+            (*gr.name2idx.get("C").unwrap(), 1.0),
+        ].into_iter().collect();
+
+        let order = gr.best_first("A".to_string(), |idx| costs[&idx]).unwrap();
+        assert_eq!(order, vec!["A".to_string(), "C".to_string(), "B".to_string()]);
+    }
 }